@@ -0,0 +1,194 @@
+use crate::Array2;
+use anyhow::{anyhow, Error, Result};
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+/// Shared implementation for [`read_csv`] and [`read_csv_columns`]: streams
+/// the file once, parsing only the columns listed in `keep` (all of them,
+/// in file order, when `keep` is `None`). Cells outside `keep` are split
+/// out for the row-length check but never passed to `f64::parse`, so
+/// selecting a handful of parameters out of a wide Stan run doesn't cost
+/// the parse time, or the memory, of the columns not asked for.
+///
+/// The first non-comment line is treated as the header and gives the
+/// parameter names; lines beginning with `#` are skipped, including both
+/// Stan's leading adaptation comments and its trailing timing comments.
+/// Malformed numeric cells produce a descriptive error naming the
+/// offending line and column rather than panicking.
+fn read_csv_selected(path: &Path, keep: Option<&[&str]>) -> Result<(Vec<String>, Array2), Error> {
+    let f = File::open(path).map_err(|e| anyhow!("Could not open {}: {}", path.display(), e))?;
+    let reader = BufReader::new(f);
+
+    let mut header_len = None;
+    let mut indices: Vec<usize> = Vec::new();
+    let mut selected_names: Vec<String> = Vec::new();
+    let mut columns: Array2 = Vec::new();
+
+    for (idx, line) in reader.lines().enumerate() {
+        let line_no = idx + 1;
+        let line =
+            line.map_err(|e| anyhow!("{}:{}: error reading line: {}", path.display(), line_no, e))?;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        if header_len.is_none() {
+            let header: Vec<String> = trimmed.split(',').map(|s| s.trim().to_string()).collect();
+            header_len = Some(header.len());
+            indices = match keep {
+                Some(params) => params
+                    .iter()
+                    .map(|&param| {
+                        header.iter().position(|n| n == param).ok_or_else(|| {
+                            anyhow!("{}: no such parameter column '{}'", path.display(), param)
+                        })
+                    })
+                    .collect::<Result<Vec<usize>, Error>>()?,
+                None => (0..header.len()).collect(),
+            };
+            selected_names = indices.iter().map(|&i| header[i].clone()).collect();
+            columns = vec![Vec::new(); indices.len()];
+            continue;
+        }
+
+        let header_len = header_len.unwrap();
+        let cells: Vec<&str> = trimmed.split(',').collect();
+        if cells.len() != header_len {
+            return Err(anyhow!(
+                "{}:{}: row has {} columns, expected {} (matching the header)",
+                path.display(),
+                line_no,
+                cells.len(),
+                header_len
+            ));
+        }
+
+        for (out_col, &src_col) in indices.iter().enumerate() {
+            let value = cells[src_col].trim();
+            let parsed: f64 = value.parse().map_err(|_| {
+                anyhow!(
+                    "{}:{}:{}: could not parse '{}' as a number",
+                    path.display(),
+                    line_no,
+                    src_col + 1,
+                    value
+                )
+            })?;
+            columns[out_col].push(parsed);
+        }
+    }
+
+    if header_len.is_none() {
+        return Err(anyhow!("{}: file has no header row", path.display()));
+    }
+    Ok((selected_names, columns))
+}
+
+/// Parse a Stan-style (or generic) CSV sample file into parameter names and
+/// their columns of draws.
+pub fn read_csv(path: &Path) -> Result<(Vec<String>, Array2), Error> {
+    read_csv_selected(path, None)
+}
+
+/// Like [`read_csv`], but streams the file once and parses only the named
+/// columns, in the order requested, so a single parameter can be diagnosed
+/// without materializing or parsing every column in the file.
+pub fn read_csv_columns(path: &Path, params: &[&str]) -> Result<(Vec<String>, Array2), Error> {
+    read_csv_selected(path, Some(params))
+}
+
+/// Read a single parameter column out of each of `paths`, one chain per
+/// file, ready to hand straight to [`crate::rhat`] or [`crate::ess_bulk`]
+/// for a multi-chain Stan run.
+pub fn read_chains(paths: &[impl AsRef<Path>], param: &str) -> Result<Array2, Error> {
+    let mut chains = Vec::with_capacity(paths.len());
+    for path in paths {
+        let (_, mut columns) = read_csv_columns(path.as_ref(), &[param])?;
+        chains.push(columns.remove(0));
+    }
+    Ok(chains)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        let mut f = File::create(&path).unwrap();
+        f.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_csv_skips_comments_and_parses_header() {
+        let path = write_temp(
+            "mcmc_rs_test_read_csv.csv",
+            "# Adaptation terminated\nlp__,mu,sigma\n1.0,2.0,3.0\n4.0,5.0,6.0\n# Elapsed Time: 1.0 seconds\n",
+        );
+        let (names, columns) = read_csv(&path).unwrap();
+        assert_eq!(names, vec!["lp__", "mu", "sigma"]);
+        assert_eq!(columns, vec![vec![1.0, 4.0], vec![2.0, 5.0], vec![3.0, 6.0]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_csv_errors_on_malformed_cell() {
+        let path = write_temp("mcmc_rs_test_bad_cell.csv", "mu,sigma\n1.0,not_a_number\n");
+        let err = read_csv(&path).unwrap_err();
+        assert!(err.to_string().contains("could not parse"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_csv_errors_on_short_row() {
+        let path = write_temp(
+            "mcmc_rs_test_short_row.csv",
+            "a,b,c\n1.0,2.0,3.0\n4.0,5.0\n",
+        );
+        let err = read_csv(&path).unwrap_err();
+        assert!(err.to_string().contains("expected 3"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_csv_columns_selects_subset() {
+        let path = write_temp(
+            "mcmc_rs_test_columns.csv",
+            "lp__,mu,sigma\n1.0,2.0,3.0\n4.0,5.0,6.0\n",
+        );
+        let (names, columns) = read_csv_columns(&path, &["sigma", "mu"]).unwrap();
+        assert_eq!(names, vec!["sigma", "mu"]);
+        assert_eq!(columns, vec![vec![3.0, 6.0], vec![2.0, 5.0]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_csv_columns_skips_parsing_unselected_cells() {
+        // A malformed cell in a column that wasn't asked for shouldn't be
+        // parsed at all, let alone fail the read.
+        let path = write_temp(
+            "mcmc_rs_test_columns_skip_parse.csv",
+            "lp__,mu,sigma\nnot_a_number,2.0,3.0\ngarbage,5.0,6.0\n",
+        );
+        let (names, columns) = read_csv_columns(&path, &["mu", "sigma"]).unwrap();
+        assert_eq!(names, vec!["mu", "sigma"]);
+        assert_eq!(columns, vec![vec![2.0, 5.0], vec![3.0, 6.0]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_read_chains_one_chain_per_file() {
+        let path_a = write_temp("mcmc_rs_test_chain_a.csv", "mu\n1.0\n2.0\n");
+        let path_b = write_temp("mcmc_rs_test_chain_b.csv", "mu\n3.0\n4.0\n");
+        let chains = read_chains(&[path_a.clone(), path_b.clone()], "mu").unwrap();
+        assert_eq!(chains, vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+        std::fs::remove_file(&path_a).unwrap();
+        std::fs::remove_file(&path_b).unwrap();
+    }
+}
@@ -0,0 +1,121 @@
+use crate::utils::quantile;
+use anyhow::{anyhow, Error, Result};
+
+/// Equal-width binning of a flattened chain, for eyeballing marginal
+/// posterior shape and spotting multimodality or a stuck sampler.
+///
+/// Before binning, draws more than 1.5 IQRs outside the `[Q1, Q3]` box are
+/// rejected as outliers so a few extreme values don't collapse all the mass
+/// into a single bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    /// Count of surviving draws in each bin.
+    pub bins: Vec<usize>,
+    /// Bin edges, `bin_count + 1` of them, from the first bin's lower
+    /// bound to the last bin's upper bound.
+    pub boundaries: Vec<f64>,
+    min: f64,
+    width: f64,
+}
+
+impl Histogram {
+    /// Build a histogram with `bin_count` equal-width bins over `draws`,
+    /// after discarding outliers via the IQR rule.
+    pub fn new(draws: &[f64], bin_count: usize) -> Result<Histogram, Error> {
+        if draws.is_empty() {
+            return Err(anyhow!("Can't build a histogram from an empty array"));
+        }
+        if bin_count == 0 {
+            return Err(anyhow!("bin_count must be greater than 0"));
+        }
+
+        let q1 = quantile(draws, 0.25)?;
+        let q3 = quantile(draws, 0.75)?;
+        let iqr = q3 - q1;
+        let lower = q1 - 1.5 * iqr;
+        let upper = q3 + 1.5 * iqr;
+        let kept: Vec<f64> = draws
+            .iter()
+            .copied()
+            .filter(|x| *x >= lower && *x <= upper)
+            .collect();
+        if kept.is_empty() {
+            return Err(anyhow!("No draws survived outlier rejection"));
+        }
+
+        let min = kept.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = kept.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let width = (max - min) / bin_count as f64;
+
+        let mut histogram = Histogram {
+            bins: vec![0; bin_count],
+            boundaries: (0..=bin_count).map(|i| min + i as f64 * width).collect(),
+            min,
+            width,
+        };
+        for x in kept {
+            let bin = histogram.to_bin(x).expect("surviving draw must land in a bin");
+            histogram.bins[bin] += 1;
+        }
+        Ok(histogram)
+    }
+
+    /// Look up which bin `x` falls into, or `None` if it's outside the
+    /// histogram's range.
+    ///
+    /// The bounds check allows a small float epsilon of slack: the upper
+    /// edge is recomputed as `min + bin_count * width`, so the largest
+    /// surviving draw can otherwise land just outside it due to
+    /// floating-point rounding in `width`.
+    pub fn to_bin(&self, x: f64) -> Option<usize> {
+        let upper = *self.boundaries.last().unwrap();
+        let eps = 1e-9 * self.min.abs().max(upper.abs()).max(1.0);
+        if x < self.min - eps || x > upper + eps {
+            return None;
+        }
+        if self.width == 0.0 {
+            return Some(0);
+        }
+        let idx = ((x - self.min) / self.width) as usize;
+        Some(idx.min(self.bins.len() - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_basic() {
+        let draws: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let hist = Histogram::new(&draws, 10).unwrap();
+        assert_eq!(hist.bins.len(), 10);
+        assert_eq!(hist.boundaries.len(), 11);
+        assert_eq!(hist.bins.iter().sum::<usize>(), 100);
+    }
+
+    #[test]
+    fn test_histogram_rejects_outliers() {
+        let mut draws: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        draws.push(10_000.0);
+        let hist = Histogram::new(&draws, 10).unwrap();
+        // The outlier should be dropped rather than stretching the bins.
+        assert_eq!(hist.bins.iter().sum::<usize>(), 100);
+        assert!(*hist.boundaries.last().unwrap() < 1000.0);
+    }
+
+    #[test]
+    fn test_histogram_errors() {
+        let empty: Vec<f64> = vec![];
+        assert!(Histogram::new(&empty, 10).is_err());
+        assert!(Histogram::new(&[1.0, 2.0, 3.0], 0).is_err());
+    }
+
+    #[test]
+    fn test_to_bin_out_of_range() {
+        let draws: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let hist = Histogram::new(&draws, 10).unwrap();
+        assert_eq!(hist.to_bin(-10.0), None);
+        assert_eq!(hist.to_bin(1000.0), None);
+    }
+}
@@ -0,0 +1,24 @@
+//! Utilities for summarizing and diagnosing MCMC sampler output: basic
+//! statistics, chain splitting, and CSV ingestion for Stan-style sample
+//! files.
+
+#[macro_use]
+extern crate approx;
+
+mod csv;
+mod histogram;
+mod rhat;
+mod utils;
+
+pub use csv::{read_chains, read_csv, read_csv_columns};
+pub use histogram::Histogram;
+pub use rhat::{ess_bulk, ess_tail, rhat};
+pub use utils::{
+    accurate_sum, flatten, median, quantile, split_chains, summary, OnlineStats, Summary,
+};
+
+/// A single chain of scalar draws.
+pub type Array1 = Vec<f64>;
+
+/// A collection of chains, each itself an [`Array1`].
+pub type Array2 = Vec<Array1>;
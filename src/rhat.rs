@@ -0,0 +1,322 @@
+use crate::utils::{accurate_sum, flatten, mean, median, sample_variance, split_chains};
+use crate::Array2;
+use anyhow::{anyhow, Error, Result};
+
+/// Inverse standard normal CDF, via Acklam's rational approximation.
+/// Accurate to roughly 1e-9, which is adequate for rank-normalizing draws.
+fn phi_inv(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e+01,
+        2.209460984245205e+02,
+        -2.759285104469687e+02,
+        1.383577518672690e+02,
+        -3.066479806614716e+01,
+        2.506628277459239e+00,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e+01,
+        1.615858368580409e+02,
+        -1.556989798598866e+02,
+        6.680131188771972e+01,
+        -1.328068155288572e+01,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-03,
+        -3.223964580411365e-01,
+        -2.400758277161838e+00,
+        -2.549732539343734e+00,
+        4.374664141464968e+00,
+        2.938163982698783e+00,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-03,
+        3.224671290700398e-01,
+        2.445134137142996e+00,
+        3.754408661907416e+00,
+    ];
+    let p_low = 0.02425;
+    let p_high = 1.0 - p_low;
+
+    if p < p_low {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= p_high {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        let q = (-2.0 * (1.0 - p).ln()).sqrt();
+        -(((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    }
+}
+
+/// Rank-normalize pooled draws: fractional ranks with ties averaged,
+/// mapped through the inverse normal CDF as in Vehtari et al. (2021).
+fn rank_normalize(pooled: &[f64]) -> Vec<f64> {
+    let n = pooled.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| pooled[a].partial_cmp(&pooled[b]).unwrap());
+
+    let mut ranks = vec![0.0; n];
+    let mut i = 0;
+    while i < n {
+        let mut j = i;
+        while j + 1 < n && pooled[order[j + 1]] == pooled[order[i]] {
+            j += 1;
+        }
+        // 1-indexed average rank over the tied run [i, j].
+        let avg_rank = (i + j) as f64 / 2.0 + 1.0;
+        for &k in &order[i..=j] {
+            ranks[k] = avg_rank;
+        }
+        i = j + 1;
+    }
+
+    let s = n as f64;
+    ranks
+        .iter()
+        .map(|&r| phi_inv((r - 3.0 / 8.0) / (s - 1.0 / 4.0)))
+        .collect()
+}
+
+/// Split pooled ranks back out into per-chain vectors matching `chains`'
+/// lengths.
+fn unflatten(pooled: &[f64], chains: &Array2) -> Array2 {
+    let mut out = Vec::with_capacity(chains.len());
+    let mut offset = 0;
+    for chain in chains {
+        out.push(pooled[offset..offset + chain.len()].to_vec());
+        offset += chain.len();
+    }
+    out
+}
+
+/// Pool all the draws in `chains`, rank-normalize them, and reshape the
+/// result back into `chains`' per-chain layout. Does not split the input;
+/// use this when `chains` is already split (e.g. [`ess_tail`]'s folded
+/// draws), and [`rank_normalized_chains`] otherwise.
+fn rank_normalize_chains(chains: &Array2) -> Array2 {
+    let pooled = flatten(chains);
+    let z = rank_normalize(&pooled);
+    unflatten(&z, chains)
+}
+
+/// Split `chains`, pool all the draws, and rank-normalize them, returning
+/// the result reshaped back into the (now doubled) per-chain layout.
+fn rank_normalized_chains(chains: &Array2) -> Result<Array2, Error> {
+    let split = split_chains(chains.clone())?;
+    Ok(rank_normalize_chains(&split))
+}
+
+/// Between-chain variance `B`, within-chain variance `W`, and the pooled
+/// variance estimate `var_hat`, per the standard split-R-hat formula.
+fn between_within_variance(chains: &Array2) -> Result<(f64, f64, f64), Error> {
+    let m = chains.len() as f64;
+    let n = chains[0].len() as f64;
+    if chains.iter().any(|c| c.len() != n as usize) {
+        return Err(anyhow!("All chains must have the same number of draws"));
+    }
+
+    let chain_means = chains
+        .iter()
+        .map(|c| mean(c))
+        .collect::<Result<Vec<f64>, Error>>()?;
+    let grand_mean = accurate_sum(&chain_means) / m;
+    let between_devs: Vec<f64> = chain_means
+        .iter()
+        .map(|cm| (cm - grand_mean).powi(2))
+        .collect();
+    let b = n / (m - 1.0) * accurate_sum(&between_devs);
+
+    let chain_vars = chains
+        .iter()
+        .map(|c| sample_variance(c))
+        .collect::<Result<Vec<f64>, Error>>()?;
+    let w = accurate_sum(&chain_vars) / m;
+
+    let var_hat = (n - 1.0) / n * w + b / n;
+    Ok((b, w, var_hat))
+}
+
+/// Biased (divide-by-n) autocovariance of `chain` at every lag from 0 to
+/// `n - 1`.
+fn autocovariance(chain: &[f64]) -> Result<Vec<f64>, Error> {
+    let n = chain.len();
+    let xbar = mean(chain)?;
+    let mut acov = vec![0.0; n];
+    for (t, slot) in acov.iter_mut().enumerate() {
+        let terms: Vec<f64> = (0..(n - t))
+            .map(|i| (chain[i] - xbar) * (chain[i + t] - xbar))
+            .collect();
+        *slot = accurate_sum(&terms) / n as f64;
+    }
+    Ok(acov)
+}
+
+/// Effective sample size from a set of equal-length chains, using the
+/// autocorrelation-based estimator summed via Geyer's initial positive
+/// sequence: consecutive pairs of autocorrelations are added to the sum
+/// until a pair turns negative.
+fn ess_from_chains(chains: &Array2) -> Result<f64, Error> {
+    let m = chains.len();
+    let n = chains[0].len();
+    if chains.iter().any(|c| c.len() != n) {
+        return Err(anyhow!("All chains must have the same number of draws"));
+    }
+    let (_, w, var_hat) = between_within_variance(chains)?;
+    if w == 0.0 {
+        return Err(anyhow!("Can't estimate ESS when within-chain variance is 0"));
+    }
+
+    let acovs = chains
+        .iter()
+        .map(|c| autocovariance(c))
+        .collect::<Result<Vec<Vec<f64>>, Error>>()?;
+    let mut rho_hat = vec![0.0; n];
+    for (t, slot) in rho_hat.iter_mut().enumerate() {
+        let mean_acov_t = acovs.iter().map(|a| a[t]).sum::<f64>() / m as f64;
+        *slot = 1.0 - (w - mean_acov_t) / var_hat;
+    }
+
+    let mut tau = 1.0;
+    let mut t = 1;
+    while t + 1 < n {
+        let pair_sum = rho_hat[t] + rho_hat[t + 1];
+        if pair_sum < 0.0 {
+            break;
+        }
+        tau += 2.0 * pair_sum;
+        t += 2;
+    }
+
+    Ok((m * n) as f64 / tau)
+}
+
+/// Rank-normalized split-R-hat: the modern, reparameterization-invariant
+/// replacement for the classic scalar R-hat, reliable for heavy-tailed or
+/// non-stationary posteriors.
+pub fn rhat(chains: &Array2) -> Result<f64, Error> {
+    let z_chains = rank_normalized_chains(chains)?;
+    let (_, w, var_hat) = between_within_variance(&z_chains)?;
+    Ok((var_hat / w).sqrt())
+}
+
+/// Bulk effective sample size: ESS of the rank-normalized draws, measuring
+/// how well the center of the posterior has been explored.
+pub fn ess_bulk(chains: &Array2) -> Result<f64, Error> {
+    ess_from_chains(&rank_normalized_chains(chains)?)
+}
+
+/// Tail effective sample size: ESS of the rank-normalized, median-folded
+/// draws (`|x - median(x)|`), measuring how well the tails have been
+/// explored.
+pub fn ess_tail(chains: &Array2) -> Result<f64, Error> {
+    let split = split_chains(chains.clone())?;
+    let pooled = flatten(&split);
+    let med = median(&pooled)?;
+    let folded: Array2 = split
+        .iter()
+        .map(|c| c.iter().map(|x| (x - med).abs()).collect())
+        .collect();
+    let z_folded = rank_normalize_chains(&folded);
+    ess_from_chains(&z_folded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converged_chains() -> Array2 {
+        // Four well-mixed chains drawn from (effectively) the same
+        // distribution should have R-hat close to 1 and healthy ESS.
+        let mut chains = Vec::new();
+        for seed in 0..4 {
+            let chain: Vec<f64> = (0..500)
+                .map(|i| ((i * 2654435761u64.wrapping_add(seed)) % 1000) as f64 / 1000.0)
+                .collect();
+            chains.push(chain);
+        }
+        chains
+    }
+
+    #[test]
+    fn test_rhat_converged_chains_near_one() {
+        let chains = converged_chains();
+        let r = rhat(&chains).unwrap();
+        assert!((r - 1.0).abs() < 0.05, "rhat was {}", r);
+    }
+
+    #[test]
+    fn test_rhat_detects_non_convergence() {
+        // Chains centered on wildly different means should not look converged.
+        let chains = vec![
+            vec![0.0; 100],
+            vec![0.01; 100],
+            vec![100.0; 100],
+            vec![100.01; 100],
+        ];
+        // The within-chain variance here is ~0, which is degenerate; use
+        // slightly jittered chains instead so variance is well-defined.
+        let chains: Array2 = chains
+            .iter()
+            .enumerate()
+            .map(|(ci, c)| {
+                c.iter()
+                    .enumerate()
+                    .map(|(i, x)| x + 0.001 * ((i + ci) % 7) as f64)
+                    .collect()
+            })
+            .collect();
+        let r = rhat(&chains).unwrap();
+        assert!(r > 1.1, "expected non-convergence, rhat was {}", r);
+    }
+
+    #[test]
+    fn test_ess_bulk_and_tail_positive() {
+        let chains = converged_chains();
+        let bulk = ess_bulk(&chains).unwrap();
+        let tail = ess_tail(&chains).unwrap();
+        assert!(bulk > 0.0);
+        assert!(tail > 0.0);
+    }
+
+    #[test]
+    fn test_rhat_errors_on_empty() {
+        let chains: Array2 = vec![];
+        assert!(rhat(&chains).is_err());
+    }
+
+    #[test]
+    fn test_ess_tail_splits_only_once() {
+        // Tail-ESS should be computed over the same split (halved) chain
+        // length as bulk-ESS, not split a second time down to quarters.
+        let chains = converged_chains();
+        let split = split_chains(chains.clone()).unwrap();
+        let folded: Array2 = split
+            .iter()
+            .map(|c| {
+                let pooled = flatten(&split);
+                let med = median(&pooled).unwrap();
+                c.iter().map(|x| (x - med).abs()).collect()
+            })
+            .collect();
+        let z_folded = rank_normalize_chains(&folded);
+        assert_eq!(z_folded.len(), split.len());
+        assert_eq!(z_folded[0].len(), split[0].len());
+        assert_abs_diff_eq!(
+            ess_tail(&chains).unwrap(),
+            ess_from_chains(&z_folded).unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
+    #[test]
+    fn test_ess_from_chains_errors_on_ragged_input() {
+        let chains = vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0]];
+        assert!(ess_from_chains(&chains).is_err());
+        assert!(between_within_variance(&chains).is_err());
+    }
+}
@@ -1,17 +1,109 @@
 use crate::{Array1, Array2};
 use anyhow::{anyhow, Error, Result};
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    path::PathBuf,
-};
+use std::iter::FromIterator;
+
+/// Streaming mean/variance accumulator using Welford's online algorithm.
+///
+/// Unlike [`mean`] and [`sample_variance`], which require the full set of
+/// draws to be held in memory as a slice, `OnlineStats` consumes draws one
+/// at a time in O(1) space, so it can be fed by a chain streamed off disk.
+/// Per-chain accumulators can later be combined with [`OnlineStats::merge`]
+/// to get pooled statistics without re-reading the underlying draws.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OnlineStats {
+    n: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl OnlineStats {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        OnlineStats::default()
+    }
+
+    /// Fold a single draw into the running mean and variance.
+    pub fn add(&mut self, x: f64) {
+        self.n += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.n as f64;
+        let delta2 = x - self.mean;
+        self.m2 += delta * delta2;
+    }
+
+    /// Number of draws folded into the accumulator so far.
+    pub fn count(&self) -> u64 {
+        self.n
+    }
+
+    /// The running mean of the draws seen so far.
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The running sample variance (Bessel's correction) of the draws seen
+    /// so far. Errs if fewer than two draws have been added.
+    pub fn sample_variance(&self) -> Result<f64, Error> {
+        if self.n < 2 {
+            return Err(anyhow!("Can't take variance of fewer than two draws"));
+        }
+        Ok(self.m2 / (self.n - 1) as f64)
+    }
+
+    /// Combine this accumulator with another, as if both had consumed the
+    /// same draws in sequence, using the Chan et al. parallel-variance
+    /// combination formula.
+    pub fn merge(&self, other: &OnlineStats) -> OnlineStats {
+        if self.n == 0 {
+            return *other;
+        }
+        if other.n == 0 {
+            return *self;
+        }
+        let n = self.n + other.n;
+        let delta = other.mean - self.mean;
+        let mean = self.mean + delta * (other.n as f64 / n as f64);
+        let m2 = self.m2
+            + other.m2
+            + delta * delta * (self.n as f64 * other.n as f64 / n as f64);
+        OnlineStats { n, mean, m2 }
+    }
+}
+
+impl FromIterator<f64> for OnlineStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = OnlineStats::new();
+        for x in iter {
+            stats.add(x);
+        }
+        stats
+    }
+}
+
+/// Sum an array with Neumaier's improvement on Kahan compensated summation,
+/// so precision doesn't degrade across the tens of thousands of draws
+/// typical of MCMC output.
+pub fn accurate_sum(arr: &[f64]) -> f64 {
+    let mut sum = 0.0;
+    let mut c = 0.0;
+    for &x in arr {
+        let t = sum + x;
+        if sum.abs() >= x.abs() {
+            c += (sum - t) + x;
+        } else {
+            c += (x - t) + sum;
+        }
+        sum = t;
+    }
+    sum + c
+}
 
 /// Compute the arithmetic mean of an array.
 pub(in crate) fn mean(arr: &[f64]) -> Result<f64, Error> {
     if arr.is_empty() {
         return Err(anyhow!("Can't take mean of empty array"));
     }
-    let sum = arr.iter().sum::<f64>();
+    let sum = accurate_sum(arr);
     let count = arr.len() as f64;
     Ok(sum / count)
 }
@@ -19,11 +111,73 @@ pub(in crate) fn mean(arr: &[f64]) -> Result<f64, Error> {
 /// Compute the sample variance of an array using Bessel's correction.
 pub(in crate) fn sample_variance(arr: &[f64]) -> Result<f64, Error> {
     let xbar = mean(arr)?;
-    Ok(arr.iter().map(|x| (x - xbar).powi(2)).sum::<f64>() / (arr.len() as f64 - 1.0))
+    let deviations: Vec<f64> = arr.iter().map(|x| (x - xbar).powi(2)).collect();
+    Ok(accurate_sum(&deviations) / (arr.len() as f64 - 1.0))
+}
+
+/// A posterior summary table: central tendency, spread, and the
+/// credible-interval quantiles users expect from a chain of draws.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub q05: f64,
+    pub q25: f64,
+    pub q50: f64,
+    pub q75: f64,
+    pub q95: f64,
+}
+
+/// Sort a copy of `arr`, erroring if any value is `NaN` since there's no
+/// sensible total order to sort by in that case.
+fn sorted_copy(arr: &[f64]) -> Result<Vec<f64>, Error> {
+    let mut sorted = arr.to_vec();
+    if sorted.iter().any(|x| x.is_nan()) {
+        return Err(anyhow!("Can't order an array containing NaN"));
+    }
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    Ok(sorted)
+}
+
+/// Compute the `p`-quantile of an array by linear interpolation between
+/// the order statistics bracketing rank `p * (len - 1)`, following the
+/// same convention as numpy's default `interpolation='linear'`.
+pub fn quantile(arr: &[f64], p: f64) -> Result<f64, Error> {
+    if arr.is_empty() {
+        return Err(anyhow!("Can't take quantile of empty array"));
+    }
+    if !(0.0..=1.0).contains(&p) {
+        return Err(anyhow!("Quantile p must be in [0, 1], got {}", p));
+    }
+    let sorted = sorted_copy(arr)?;
+    let n = sorted.len();
+    let h = p * (n - 1) as f64;
+    let lo = h.floor() as usize;
+    let hi = (lo + 1).min(n - 1);
+    Ok(sorted[lo] + (h - lo as f64) * (sorted[hi] - sorted[lo]))
+}
+
+/// Compute the median (50th percentile) of an array.
+pub fn median(arr: &[f64]) -> Result<f64, Error> {
+    quantile(arr, 0.5)
+}
+
+/// Summarize a flattened chain with the mean, standard deviation, and the
+/// 5%/25%/50%/75%/95% quantiles, the standard posterior summary table.
+pub fn summary(arr: &[f64]) -> Result<Summary, Error> {
+    Ok(Summary {
+        mean: mean(arr)?,
+        std_dev: sample_variance(arr)?.sqrt(),
+        q05: quantile(arr, 0.05)?,
+        q25: quantile(arr, 0.25)?,
+        q50: quantile(arr, 0.50)?,
+        q75: quantile(arr, 0.75)?,
+        q95: quantile(arr, 0.95)?,
+    })
 }
 
 /// Clone a 2D array into one long 1D array.
-pub(in crate) fn flatten(chains: &Array2) -> Array1 {
+pub fn flatten(chains: &Array2) -> Array1 {
     let mut flattened = Vec::new();
     for chain in chains {
         flattened.extend(chain);
@@ -59,33 +213,6 @@ pub fn split_chains(chains: Array2) -> Result<Array2, Error> {
     Ok(split_draws)
 }
 
-/// Simplified CSV reader for tesing purposes only; does not actually implement
-/// parsing for headers, quotation, or other more advanced features. Assumes
-/// that all values aside from the commas will be numeric.
-///
-/// # Arguments
-/// * `skip_rows` - Number of rows to skip before numeric values. For example,
-///                 if there is a header row you can pass in the value `1`.
-/// * `n_rows` - Number of rows to read in. Use if you only want a certain
-///              subset of rows or if there are improper rows after the numeric
-///              rows (e.g. in Stan sample files there are commented rows at the end).
-pub fn read_csv(path: &PathBuf, skip_rows: usize, n_rows: usize) -> Array2 {
-    let mut result: Array2 = Vec::new();
-    let f = File::open(&path).unwrap();
-    let f = BufReader::new(f);
-    for line in f.lines().skip(skip_rows).take(n_rows) {
-        if let Ok(line) = line {
-            for (idx, value) in line.split(',').enumerate() {
-                if idx >= result.len() {
-                    result.push(Vec::new())
-                }
-                result[idx].push(value.parse::<f64>().unwrap());
-            }
-        }
-    }
-    result
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +245,99 @@ mod tests {
         assert!(mean(&empty).is_err());
     }
 
+    #[test]
+    fn test_accurate_sum() {
+        // A case that loses precision under naive summation: a large value
+        // followed by many small ones that a plain running total would
+        // swallow without the compensation term.
+        let mut arr = vec![1.0e16];
+        arr.extend(std::iter::repeat(1.0).take(1000));
+        arr.push(-1.0e16);
+        assert_abs_diff_eq!(accurate_sum(&arr), 1000.0, epsilon = 1e-6);
+        assert_ne!(arr.iter().sum::<f64>(), 1000.0);
+    }
+
+    #[test]
+    fn test_quantile_and_summary() {
+        // Test against values computed with numpy's default linear interpolation.
+        let arr = vec![
+            2.13829088,
+            -1.06214379,
+            -0.79265699,
+            -0.21300888,
+            -1.07155142,
+            -0.50425317,
+            0.95708854,
+            -1.23854172,
+            1.37124938,
+            1.17658286,
+        ];
+        assert_abs_diff_eq!(quantile(&arr, 0.05).unwrap(), -1.163396085, epsilon = 1e-6);
+        assert_abs_diff_eq!(quantile(&arr, 0.25).unwrap(), -0.99477209, epsilon = 1e-6);
+        assert_abs_diff_eq!(median(&arr).unwrap(), -0.358631025, epsilon = 1e-6);
+        assert_abs_diff_eq!(quantile(&arr, 0.75).unwrap(), 1.12170928, epsilon = 1e-6);
+        assert_abs_diff_eq!(quantile(&arr, 0.95).unwrap(), 1.793122205, epsilon = 1e-6);
+
+        let s = summary(&arr).unwrap();
+        assert_abs_diff_eq!(s.mean, 0.07610557018217139, epsilon = 1e-6);
+        assert_abs_diff_eq!(s.std_dev, 1.2217184840256063, epsilon = 1e-6);
+        assert_abs_diff_eq!(s.q50, median(&arr).unwrap(), epsilon = 1e-9);
+
+        let empty: Array1 = vec![];
+        assert!(quantile(&empty, 0.5).is_err());
+        assert!(quantile(&arr, 1.5).is_err());
+        assert!(quantile(&arr, -0.1).is_err());
+
+        let with_nan = vec![1.0, f64::NAN, 2.0];
+        assert!(quantile(&with_nan, 0.5).is_err());
+    }
+
+    #[test]
+    fn test_online_stats_matches_batch() {
+        // Streaming accumulator should agree with the batch implementation.
+        let arr = vec![
+            2.13829088,
+            -1.06214379,
+            -0.79265699,
+            -0.21300888,
+            -1.07155142,
+            -0.50425317,
+            0.95708854,
+            -1.23854172,
+            1.37124938,
+            1.17658286,
+        ];
+        let stats: OnlineStats = arr.iter().copied().collect();
+        assert_abs_diff_eq!(stats.mean(), mean(&arr).unwrap(), epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            stats.sample_variance().unwrap(),
+            sample_variance(&arr).unwrap(),
+            epsilon = 1e-9
+        );
+
+        let empty = OnlineStats::new();
+        assert!(empty.sample_variance().is_err());
+    }
+
+    #[test]
+    fn test_online_stats_merge() {
+        // Merging two partial accumulators should match one pass over all the data.
+        let a = vec![1.0, 2.0, 3.0, 4.0];
+        let b = vec![5.0, 6.0, 7.0];
+        let whole: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+
+        let stats_a: OnlineStats = a.into_iter().collect();
+        let stats_b: OnlineStats = b.into_iter().collect();
+        let merged = stats_a.merge(&stats_b);
+
+        assert_abs_diff_eq!(merged.mean(), mean(&whole).unwrap(), epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            merged.sample_variance().unwrap(),
+            sample_variance(&whole).unwrap(),
+            epsilon = 1e-9
+        );
+    }
+
     #[test]
     fn test_split_empty_chains() {
         // Make sure the we Err on empty or minimum 0 length chains